@@ -0,0 +1,79 @@
+/*
+Copyright (c) 2020 Todd Stellanova
+LICENSE: BSD3 (see LICENSE file)
+*/
+
+//! `bytes::Buf` / `bytes::BufMut` integration, enabled via the `bytes` feature.
+
+use crate::ShuffleBuf;
+use bytes::buf::UninitSlice;
+use bytes::{Buf, BufMut};
+
+impl<const SIZE: usize> Buf for ShuffleBuf<SIZE> {
+    fn remaining(&self) -> usize {
+        self.available()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.peek()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        ShuffleBuf::consume(self, cnt)
+    }
+}
+
+impl<const SIZE: usize> BufMut for ShuffleBuf<SIZE> {
+    fn remaining_mut(&self) -> usize {
+        self.vacant()
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        UninitSlice::new(self.spare_capacity_mut())
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.commit(cnt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_u16_roundtrip() {
+        let mut shuffler = ShuffleBuf::<256>::default();
+        shuffler.put_u16(0x1234);
+        assert_eq!(shuffler.remaining(), 2);
+        assert_eq!(shuffler.get_u16(), 0x1234);
+        assert_eq!(shuffler.remaining(), 0);
+    }
+
+    #[test]
+    fn test_put_slice_chunk_advance() {
+        let mut shuffler = ShuffleBuf::<256>::default();
+        shuffler.put_slice(&[1, 2, 3, 4, 5]);
+        assert_eq!(shuffler.remaining(), 5);
+        assert_eq!(shuffler.chunk(), &[1, 2, 3, 4, 5]);
+
+        shuffler.advance(2);
+        assert_eq!(shuffler.chunk(), &[3, 4, 5]);
+
+        // advance() triggers a shuffle once read_idx is non-zero, so further
+        // writes still land in a contiguous chunk.
+        shuffler.put_slice(&[6, 7]);
+        assert_eq!(shuffler.chunk(), &[3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_chunk_mut_remaining_mut() {
+        let mut shuffler = ShuffleBuf::<8>::default();
+        assert_eq!(shuffler.remaining_mut(), 8);
+        assert_eq!(shuffler.chunk_mut().len(), 8);
+
+        shuffler.put_slice(&[1, 2, 3]);
+        assert_eq!(shuffler.remaining_mut(), 5);
+        assert_eq!(shuffler.chunk_mut().len(), 5);
+    }
+}
@@ -0,0 +1,131 @@
+/*
+Copyright (c) 2020 Todd Stellanova
+LICENSE: BSD3 (see LICENSE file)
+*/
+
+//! DMA-friendly alignment support, for embedded peripherals (NIC/DMA engines
+//! in the zynq/smoltcp style) that require cache-line-aligned, non-split
+//! buffer regions.
+
+use crate::ShuffleBuf;
+use core::ops::{Deref, DerefMut};
+
+/// A [`ShuffleBuf`] whose backing storage starts on a 64-byte (cache-line)
+/// boundary.
+///
+/// `ShuffleBuf` is `repr(C)` with `buf` as its first field, so aligning this
+/// wrapper struct also aligns `buf` itself. Because `shuffle_up()` always
+/// relocates the remaining bytes to offset 0 of the same backing array, the
+/// alignment guarantee holds across shuffles: the array's address never
+/// changes, only the bytes within it move.
+///
+/// `DmaShuffleBuf` derefs to `ShuffleBuf`, so all the usual read/write/peek
+/// API is still available; this type only adds the DMA-specific accessors
+/// below.
+#[repr(align(64))]
+pub struct DmaShuffleBuf<const SIZE: usize>(ShuffleBuf<SIZE>);
+
+impl<const SIZE: usize> DmaShuffleBuf<SIZE> {
+    /// The alignment boundary this buffer's storage is guaranteed to start on.
+    pub const ALIGN: usize = 64;
+
+    pub fn default() -> Self {
+        Self(ShuffleBuf::default())
+    }
+
+    /// The largest aligned contiguous span available for a DMA engine to
+    /// write into, starting at the current write index.
+    ///
+    /// A region "at `write_idx`" can only be `ALIGN`-aligned if `write_idx`
+    /// itself is a multiple of `ALIGN`; otherwise this returns an empty
+    /// slice rather than handing a DMA engine an unaligned pointer.
+    /// `write_idx` is `0` right after construction, and `shuffle_up()` resets
+    /// it to `0` when it runs on an emptied buffer. `read_many` and
+    /// `consume` always run it, so draining with them restores alignment;
+    /// `read_one` runs it only once `read_idx` exceeds 4.
+    ///
+    /// Once the DMA transfer completes, call [`ShuffleBuf::commit`] with the
+    /// number of bytes actually written.
+    pub fn writable_dma_region(&mut self) -> &mut [u8] {
+        if self.0.write_idx % Self::ALIGN != 0 {
+            return &mut [];
+        }
+        self.0.spare_capacity_mut()
+    }
+
+    /// The physical address and length of the writable DMA region, for
+    /// handing to a DMA descriptor.
+    ///
+    /// As with [`Self::writable_dma_region`], this is empty (length `0`, and
+    /// the address is that of an empty slice, not a location in the buffer)
+    /// when `write_idx` is not `ALIGN`-aligned.
+    /// Once the DMA transfer completes, call [`ShuffleBuf::commit`] with the
+    /// number of bytes written.
+    pub fn dma_region_addr(&mut self) -> (usize, usize) {
+        let region = self.writable_dma_region();
+        (region.as_ptr() as usize, region.len())
+    }
+}
+
+impl<const SIZE: usize> Deref for DmaShuffleBuf<SIZE> {
+    type Target = ShuffleBuf<SIZE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const SIZE: usize> DerefMut for DmaShuffleBuf<SIZE> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_to_64_bytes() {
+        let dma = DmaShuffleBuf::<256>::default();
+        let addr = &dma.0 as *const _ as usize;
+        assert_eq!(addr % 64, 0);
+    }
+
+    #[test]
+    fn test_writable_region_when_aligned() {
+        let mut dma = DmaShuffleBuf::<256>::default();
+        assert_eq!(dma.writable_dma_region().len(), 256);
+
+        let (addr, len) = dma.dma_region_addr();
+        assert_eq!(len, 256);
+        assert_eq!(addr % DmaShuffleBuf::<256>::ALIGN, 0);
+    }
+
+    #[test]
+    fn test_writable_region_empty_when_write_idx_unaligned() {
+        let mut dma = DmaShuffleBuf::<256>::default();
+        dma.push_many(&[1, 2, 3]);
+
+        // write_idx is 3, not a multiple of ALIGN: no aligned region "at
+        // write_idx" exists, so no unaligned pointer is handed out.
+        assert!(dma.writable_dma_region().is_empty());
+        assert_eq!(dma.dma_region_addr(), (dma.writable_dma_region().as_ptr() as usize, 0));
+    }
+
+    #[test]
+    fn test_writable_region_realigns_after_drain() {
+        let mut dma = DmaShuffleBuf::<256>::default();
+        dma.push_many(&[1, 2, 3]);
+        assert!(dma.writable_dma_region().is_empty());
+
+        // Draining the pending bytes triggers shuffle_up(), which resets
+        // write_idx to 0 (ALIGN-aligned by construction).
+        let mut out = [0u8; 3];
+        dma.read_many(&mut out);
+
+        let region = dma.writable_dma_region();
+        assert_eq!(region.len(), 256);
+        assert_eq!(region.as_ptr() as usize % DmaShuffleBuf::<256>::ALIGN, 0);
+    }
+}
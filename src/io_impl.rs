@@ -0,0 +1,75 @@
+/*
+Copyright (c) 2020 Todd Stellanova
+LICENSE: BSD3 (see LICENSE file)
+*/
+
+//! `std::io::Read` / `BufRead` / `Write` integration, enabled via the `std` feature.
+
+use crate::ShuffleBuf;
+use std::io::{self, BufRead, Read, Write};
+
+impl<const SIZE: usize> Read for ShuffleBuf<SIZE> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.read_many(buf))
+    }
+}
+
+impl<const SIZE: usize> BufRead for ShuffleBuf<SIZE> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.peek())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        ShuffleBuf::consume(self, amt)
+    }
+}
+
+impl<const SIZE: usize> Write for ShuffleBuf<SIZE> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.push_many(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let mut shuffler = ShuffleBuf::<256>::default();
+        let written = shuffler.write(&[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(written, 5);
+        shuffler.flush().unwrap();
+
+        let mut out = [0u8; 5];
+        let read = shuffler.read(&mut out).unwrap();
+        assert_eq!(read, 5);
+        assert_eq!(out, [1, 2, 3, 4, 5]);
+    }
+
+    /// Proves the zero-copy `fill_buf`/`consume` contract: a record boundary
+    /// can be found and parsed in place, without copying bytes out first.
+    #[test]
+    fn test_fill_buf_consume_parses_in_place() {
+        let mut shuffler = ShuffleBuf::<256>::default();
+        shuffler.write_all(b"first;second;").unwrap();
+
+        let buf = shuffler.fill_buf().unwrap();
+        let end = buf.iter().position(|&b| b == b';').unwrap();
+        assert_eq!(&buf[..end], b"first");
+        let record_len = end + 1;
+        shuffler.consume(record_len);
+
+        let buf = shuffler.fill_buf().unwrap();
+        let end = buf.iter().position(|&b| b == b';').unwrap();
+        assert_eq!(&buf[..end], b"second");
+        let record_len = end + 1;
+        shuffler.consume(record_len);
+
+        assert_eq!(shuffler.fill_buf().unwrap(), b"");
+    }
+}
@@ -3,8 +3,23 @@ Copyright (c) 2020 Todd Stellanova
 LICENSE: BSD3 (see LICENSE file)
 */
 
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
+mod ring_buf;
+pub use ring_buf::RingBuf;
+
+#[cfg(feature = "bytes")]
+mod bytes_impl;
+
+#[cfg(feature = "std")]
+mod io_impl;
+
+mod dma;
+pub use dma::DmaShuffleBuf;
+
+/// `repr(C)` fixes `buf` as the first field at offset 0, so a wrapper that
+/// aligns the whole struct (see [`DmaShuffleBuf`]) also aligns `buf` itself.
+#[repr(C)]
 pub struct ShuffleBuf<const SIZE: usize> {
     /// The actual buffer
     buf: [u8; SIZE],
@@ -69,6 +84,38 @@ impl<const SIZE: usize> ShuffleBuf<SIZE> {
         self.buf.len() - self.write_idx
     }
 
+    /// Look at the buffered bytes without consuming them
+    pub fn peek(&self) -> &[u8] {
+        &self.buf[self.read_idx..self.write_idx]
+    }
+
+    /// Look at up to `n` of the buffered bytes without consuming them
+    pub fn peek_up_to(&self, n: usize) -> &[u8] {
+        let end = self.read_idx + n.min(self.available());
+        &self.buf[self.read_idx..end]
+    }
+
+    /// Discard `n` bytes that were previously inspected with [`Self::peek`]
+    /// or [`Self::peek_up_to`], without copying them out
+    pub fn consume(&mut self, n: usize) {
+        assert!(n <= self.available(), "n > available()");
+        self.read_idx += n;
+        self.shuffle_up();
+    }
+
+    /// The writable tail of the buffer, for filling in place before calling
+    /// [`Self::commit`]
+    pub fn spare_capacity_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[self.write_idx..]
+    }
+
+    /// Record that `n` bytes were written directly into the slice returned
+    /// by [`Self::spare_capacity_mut`]
+    pub fn commit(&mut self, n: usize) {
+        assert!(n <= self.vacant(), "n > vacant()");
+        self.write_idx += n;
+    }
+
     /// Move remaining bytes to the start of the buffer
     fn shuffle_up(&mut self) {
         if self.read_idx > 0 {
@@ -0,0 +1,256 @@
+/*
+Copyright (c) 2020 Todd Stellanova
+LICENSE: BSD3 (see LICENSE file)
+*/
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A lock-free single-producer/single-consumer ring buffer.
+///
+/// Unlike [`crate::ShuffleBuf`], `RingBuf` never relocates bytes: `read_idx` and
+/// `write_idx` grow monotonically and are only masked into the storage range
+/// when indexing. This makes it safe to share between one producer thread and
+/// one consumer thread with no locking and no `copy_within` cost.
+///
+/// `SIZE` must be a power of two, since indices are masked with `SIZE - 1`
+/// rather than wrapped with a modulo.
+///
+/// All methods take `&self`, so a single `RingBuf` can be shared (e.g. behind
+/// an `Arc`, or simply borrowed across a scoped thread) by one producer
+/// calling `push_one`/`push_many` and one consumer calling
+/// `read_one`/`read_many` concurrently. Calling the producer methods from
+/// more than one thread, or the consumer methods from more than one thread,
+/// is not supported and may corrupt the buffer.
+pub struct RingBuf<const SIZE: usize> {
+    /// The actual buffer
+    buf: UnsafeCell<[u8; SIZE]>,
+    /// The index at which the next byte should be read from the buffer.
+    /// Grows monotonically; only masked into `0..SIZE` when indexing.
+    read_idx: AtomicUsize,
+    /// The index at which the next byte should be written to the buffer.
+    /// Grows monotonically; only masked into `0..SIZE` when indexing.
+    write_idx: AtomicUsize,
+}
+
+// SAFETY: the producer (push_one/push_many) only ever writes to the vacant
+// region `[write_idx & MASK, read_idx & MASK)` and the consumer
+// (read_one/read_many) only ever reads from the available region
+// `[read_idx & MASK, write_idx & MASK)`. These two regions partition the
+// backing array and never overlap, so a producer thread and a consumer
+// thread sharing `&RingBuf` never access the same byte at the same time.
+unsafe impl<const SIZE: usize> Sync for RingBuf<SIZE> {}
+
+impl<const SIZE: usize> RingBuf<SIZE> {
+    const MASK: usize = {
+        assert!(SIZE.is_power_of_two(), "RingBuf SIZE must be a power of two");
+        SIZE - 1
+    };
+
+    pub fn default() -> Self {
+        Self {
+            buf: UnsafeCell::new([0; SIZE]),
+            read_idx: AtomicUsize::new(0),
+            write_idx: AtomicUsize::new(0),
+        }
+    }
+
+    /// Read one byte from the buffer
+    /// Returns the number of bytes returned (0 or 1)
+    pub fn read_one(&self) -> (usize, u8) {
+        let read_idx = self.read_idx.load(Ordering::Relaxed);
+        let write_idx = self.write_idx.load(Ordering::Acquire);
+        if write_idx > read_idx {
+            // SAFETY: only the consumer reads the available region; see the
+            // `Sync` impl above.
+            let val = unsafe { (*self.buf.get())[read_idx & Self::MASK] };
+            self.read_idx.store(read_idx + 1, Ordering::Release);
+            return (1_usize, val);
+        }
+        (0, 0)
+    }
+
+    /// Pull some data out of the buffer
+    /// Returns the number of bytes returned (`out_buf.len()` max)
+    pub fn read_many(&self, out_buf: &mut [u8]) -> usize {
+        let read_idx = self.read_idx.load(Ordering::Relaxed);
+        let write_idx = self.write_idx.load(Ordering::Acquire);
+        let avail = write_idx - read_idx;
+        if avail == 0 {
+            return 0;
+        }
+        let read_count = out_buf.len().min(avail);
+
+        // SAFETY: only the consumer reads the available region; see the
+        // `Sync` impl above.
+        let buf = unsafe { &*self.buf.get() };
+        let start = read_idx & Self::MASK;
+        let first_span = (SIZE - start).min(read_count);
+        out_buf[..first_span].copy_from_slice(&buf[start..start + first_span]);
+        if first_span < read_count {
+            let remaining = read_count - first_span;
+            out_buf[first_span..read_count].copy_from_slice(&buf[..remaining]);
+        }
+
+        self.read_idx.store(read_idx + read_count, Ordering::Release);
+        read_count
+    }
+
+    /// How much data is available to read?
+    pub fn available(&self) -> usize {
+        let read_idx = self.read_idx.load(Ordering::Relaxed);
+        let write_idx = self.write_idx.load(Ordering::Acquire);
+        write_idx - read_idx
+    }
+
+    /// How much space is vacant in the buffer?
+    pub fn vacant(&self) -> usize {
+        SIZE - self.available()
+    }
+
+    /// Push one byte into the buffer
+    pub fn push_one(&self, data: u8) -> usize {
+        let write_idx = self.write_idx.load(Ordering::Relaxed);
+        let read_idx = self.read_idx.load(Ordering::Acquire);
+        if write_idx - read_idx >= SIZE {
+            return 0;
+        }
+        // SAFETY: only the producer writes to the vacant region; see the
+        // `Sync` impl above.
+        unsafe {
+            (*self.buf.get())[write_idx & Self::MASK] = data;
+        }
+        self.write_idx.store(write_idx + 1, Ordering::Release);
+        1
+    }
+
+    /// Copy some data into the buffer
+    pub fn push_many(&self, data: &[u8]) -> usize {
+        let write_idx = self.write_idx.load(Ordering::Relaxed);
+        let read_idx = self.read_idx.load(Ordering::Acquire);
+        let vacant = SIZE - (write_idx - read_idx);
+        if vacant == 0 {
+            return 0;
+        }
+        let copy_count = data.len().min(vacant);
+
+        // SAFETY: only the producer writes to the vacant region; see the
+        // `Sync` impl above.
+        let buf = unsafe { &mut *self.buf.get() };
+        let start = write_idx & Self::MASK;
+        let first_span = (SIZE - start).min(copy_count);
+        buf[start..start + first_span].copy_from_slice(&data[..first_span]);
+        if first_span < copy_count {
+            let remaining = copy_count - first_span;
+            buf[..remaining].copy_from_slice(&data[first_span..copy_count]);
+        }
+
+        self.write_idx.store(write_idx + copy_count, Ordering::Release);
+        copy_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_basics() {
+        let buf_a: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        let ring = RingBuf::<256>::default();
+        let push_count = ring.push_many(&buf_a);
+        assert_eq!(push_count, buf_a.len());
+
+        let mut buf_b = [0u8; 25];
+        let read_count = ring.read_many(&mut buf_b);
+        assert_eq!(read_count, 10); //same as buf_a
+        // no more bytes left
+        let read_count = ring.read_many(&mut buf_b);
+        assert_eq!(read_count, 0);
+    }
+
+    #[test]
+    fn test_overrun() {
+        let mut buf_a: [u8; 512] = [8; 512];
+        buf_a[55] = 127;
+
+        let ring = RingBuf::<256>::default();
+        let push_count = ring.push_many(&buf_a);
+        assert_eq!(push_count, 256);
+        assert_eq!(ring.available(), 256);
+        assert_eq!(ring.vacant(), 0);
+
+        buf_a[55] = 0;
+        let read_count = ring.read_many(buf_a[..60].as_mut());
+        assert_eq!(read_count, 60);
+        assert_eq!(buf_a[55], 127); //original value
+
+        assert_eq!(ring.available(), 256 - 60);
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let ring = RingBuf::<8>::default();
+
+        let mut scratch = [0u8; 8];
+        assert_eq!(ring.push_many(&[1, 2, 3, 4, 5, 6]), 6);
+        assert_eq!(ring.read_many(&mut scratch[..4]), 4);
+        assert_eq!(&scratch[..4], &[1, 2, 3, 4]);
+
+        // write_idx is now 6, read_idx is 4; pushing 5 more bytes wraps past
+        // the physical end of the array.
+        assert_eq!(ring.push_many(&[7, 8, 9, 10, 11]), 5);
+        assert_eq!(ring.available(), 7);
+
+        let mut out = [0u8; 7];
+        assert_eq!(ring.read_many(&mut out), 7);
+        assert_eq!(out, [5, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_one_byte_buffer() {
+        let ring = RingBuf::<1>::default();
+        assert_eq!(ring.push_one(1), 1);
+        assert_eq!(ring.push_one(2), 0);
+        assert_eq!(ring.available(), 1);
+        assert_eq!(ring.vacant(), 0);
+    }
+
+    /// Shares a single `&RingBuf` (no `&mut` aliasing, no raw-pointer escape
+    /// hatch) between a real producer thread and a real consumer thread.
+    #[test]
+    fn multithread_write_read() {
+        let ring = RingBuf::<256>::default();
+
+        thread::scope(|scope| {
+            let writer = scope.spawn(|| {
+                for i in 0..100u8 {
+                    while ring.push_one(i) == 0 {
+                        thread::yield_now();
+                    }
+                    if (i % 2) == 0 {
+                        thread::yield_now();
+                    }
+                }
+            });
+
+            let mut read_bytes = [0u8; 100];
+            let mut read_count = 0;
+            while read_count < read_bytes.len() {
+                let (nread, val) = ring.read_one();
+                if nread == 0 {
+                    thread::yield_now();
+                    continue;
+                }
+                read_bytes[read_count] = val;
+                read_count += 1;
+            }
+
+            writer.join().unwrap();
+            assert_eq!(read_count, 100);
+            assert_eq!(read_bytes, core::array::from_fn(|i| i as u8));
+        });
+    }
+}